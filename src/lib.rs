@@ -1,10 +1,12 @@
 #![no_std]
 #![deny(warnings)]
+#![cfg_attr(feature = "oom", feature(alloc_error_handler))]
 
 use core::panic::PanicInfo;
 use core::fmt::Write;
 use core::mem::size_of;
 use cfg_if::cfg_if;
+use cortex_m_rt::{exception, ExceptionFrame};
 
 static mut PANIC_LED_BLINKER: Option<fn ()> = None;
 
@@ -14,6 +16,17 @@ pub fn set_panic_led_blinker(blinker: fn ()) {
     }
 }
 
+static mut PANIC_REPORTER: Option<fn(&RecoveredPanic)> = None;
+
+/// Registers a hook that `report_last_panic()` calls for each crash record
+/// recovered from RAM. The hook owns transport (RTT, a UART, defmt, ...);
+/// see `write_recovered_panic()` for a ready-made formatter to call into it.
+pub fn set_panic_reporter(reporter: fn(&RecoveredPanic)) {
+    unsafe {
+        PANIC_REPORTER = Some(reporter);
+    }
+}
+
 struct DumbCursor<'a> {
     pub buf: &'a mut[u8],
     pub idx: usize
@@ -41,6 +54,21 @@ extern "C" {
     pub static mut _panic_info_ram_end: u8;
 }
 
+cfg_if! {
+    if #[cfg(all(feature = "backtrace", not(feature = "frame-pointer")))] {
+        extern "C" {
+            pub static _stack_start: u8;
+            pub static _stext: u8;
+            pub static _etext: u8;
+        }
+    } else if #[cfg(feature = "backtrace")] {
+        extern "C" {
+            pub static _stext: u8;
+            pub static _etext: u8;
+        }
+    }
+}
+
 pub fn ram_log_slice() -> &'static mut[u8] {
     unsafe {
         let panic_info_ram_start = &mut _panic_info_ram_start as *mut u8;
@@ -50,49 +78,431 @@ pub fn ram_log_slice() -> &'static mut[u8] {
     }
 }
 
-/// RAM layout: PanicInfoRam struct | filename (0 or more bytes) | message
+/// Discriminates what kind of crash a `PanicInfoMeta` record describes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+pub enum FaultCause {
+    Panic = 0,
+    HardFault = 1,
+    Oom = 2,
+}
+
+impl Default for FaultCause {
+    fn default() -> Self {
+        FaultCause::Panic
+    }
+}
+
+impl FaultCause {
+    fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(FaultCause::Panic),
+            1 => Some(FaultCause::HardFault),
+            2 => Some(FaultCause::Oom),
+            _ => None
+        }
+    }
+}
+
+/// Stacked exception frame and fault status registers captured by the
+/// `HardFault` handler. Laid out right after `PanicInfoMeta` in RAM, in
+/// place of the filename/message pair that a `Panic` record carries.
+#[derive(Default, Debug, Copy, Clone)]
+#[repr(C)]
+pub struct HardFaultRegs {
+    pub cfsr: u32,
+    pub hfsr: u32,
+    pub mmfar: u32,
+    pub bfar: u32,
+    pub r0: u32,
+    pub r1: u32,
+    pub r2: u32,
+    pub r3: u32,
+    pub r12: u32,
+    pub lr: u32,
+    pub pc: u32,
+    pub xpsr: u32,
+}
+
+/// Failed allocation `Layout` captured by the optional `oom` feature's
+/// `#[alloc_error_handler]`. Laid out right after `PanicInfoMeta`, like
+/// `HardFaultRegs`.
+#[derive(Default, Debug, Copy, Clone)]
+#[repr(C)]
+pub struct OomInfo {
+    pub size: u32,
+    pub align: u32,
+}
+
+/// A crash record recovered from the RAM log ring buffer on boot.
+#[derive(Debug, Copy, Clone)]
+pub enum RecoveredPanic {
+    Panic(PanicRecord),
+    HardFault(HardFaultRegs),
+    Oom(OomInfo),
+}
+
+/// A recovered panic record together with enough context to read back its
+/// filename/message from the slot it was stored in.
+#[derive(Debug, Copy, Clone)]
+pub struct PanicRecord {
+    meta: PanicInfoMeta,
+    payload_offset: usize,
+}
+
+impl PanicRecord {
+    pub fn line(&self) -> u32 {
+        self.meta.line
+    }
+
+    pub fn column(&self) -> u32 {
+        self.meta.column
+    }
+
+    pub fn filename(&self) -> &'static str {
+        unsafe {
+            let panic_info_ram = ram_log_slice();
+            let bytes = &panic_info_ram[self.payload_offset..self.payload_offset + self.meta.filename_len as usize];
+            core::str::from_utf8_unchecked(bytes)
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        unsafe {
+            let panic_info_ram = ram_log_slice();
+            let start = self.payload_offset + self.meta.filename_len as usize;
+            let bytes = &panic_info_ram[start..start + self.meta.message_len as usize];
+            core::str::from_utf8_unchecked(bytes)
+        }
+    }
+
+    /// Raw return addresses captured at panic time (see the `backtrace`
+    /// feature), innermost call first. Empty if the feature is disabled or no
+    /// frames were found. Symbolize these offline against the firmware ELF.
+    pub fn frames(&self) -> impl Iterator<Item = u32> + 'static {
+        let start = self.payload_offset + self.meta.filename_len as usize + self.meta.message_len as usize;
+        let len = self.meta.frames_len as usize;
+        // filename_len + message_len is rarely a multiple of 4, so this byte
+        // offset isn't necessarily u32-aligned: read each frame unaligned
+        // rather than reinterpreting the payload as a `[u32]`.
+        (0..len).map(move |i| unsafe {
+            let panic_info_ram = ram_log_slice();
+            let addr = panic_info_ram.as_ptr().add(start + i * size_of::<u32>());
+            core::ptr::read_unaligned(addr as *const u32)
+        })
+    }
+}
+
+/// Slot layout: PanicInfoMeta | filename (0 or more bytes) | message |
+/// frames (`frames_len` u32 return addresses)
+/// (for `cause == HardFault`, a `HardFaultRegs`, and for `cause == Oom`, an
+/// `OomInfo` is stored in place of the filename/message/frames instead).
 #[derive(Default, Debug, Copy, Clone)]
+#[repr(C)]
 pub struct PanicInfoMeta {
+    pub cause: u8,
     pub filename_len: u8,
     pub line: u32,
     pub column: u32,
     pub message_len: u16,
-    pub xor: u8
+    pub frames_len: u8,
+    pub crc32: u32
 }
 
 impl PanicInfoMeta {
-    pub fn detect_and_reset() -> Option<Self> {
-        let panic_info_ram = ram_log_slice();
-        unsafe {
-            let panic_info_meta = *(panic_info_ram.as_mut_ptr() as *const PanicInfoMeta);
-            let mut xor = 0;
-            for i in size_of::<PanicInfoMeta>()..panic_info_ram.len() {
-                xor = xor ^ panic_info_ram.get_unchecked(i);
+    /// Number of payload bytes following this header that belong to the
+    /// record, based on `cause`. Unknown causes report an empty payload so
+    /// callers bail out before trusting any of the slot's contents.
+    fn payload_len(&self) -> usize {
+        match FaultCause::from_u8(self.cause) {
+            Some(FaultCause::Panic) => {
+                self.filename_len as usize + self.message_len as usize
+                    + self.frames_len as usize * size_of::<u32>()
             }
-            if xor == panic_info_meta.xor {
-                core::ptr::write_bytes(panic_info_ram.as_mut_ptr(), 0, size_of::<PanicInfoMeta>());
-                Some(panic_info_meta)
-            } else {
-                None
+            Some(FaultCause::HardFault) => size_of::<HardFaultRegs>(),
+            Some(FaultCause::Oom) => size_of::<OomInfo>(),
+            None => 0,
+        }
+    }
+}
+
+/// Bumped whenever the RAM record layout changes incompatibly, so a record
+/// written by an older/newer build is rejected instead of misread.
+const RING_LAYOUT_VERSION: u8 = 1;
+const RING_MAGIC: u32 = 0x474F_4C52; // "RLOG"
+
+/// How many crash records the ring buffer retains before the oldest entries
+/// are overwritten by new ones.
+pub const RING_CAPACITY: usize = 4;
+
+/// RAM layout: RingHeader | slot 0 | slot 1 | ... | slot RING_CAPACITY - 1
+#[derive(Default, Debug, Copy, Clone)]
+#[repr(C)]
+struct RingHeader {
+    magic: u32,
+    layout_version: u8,
+    write_idx: u8,
+    wrap_count: u8,
+}
+
+impl RingHeader {
+    fn is_valid(&self) -> bool {
+        self.magic == RING_MAGIC && self.layout_version == RING_LAYOUT_VERSION
+    }
+}
+
+fn ring_header() -> &'static mut RingHeader {
+    unsafe { &mut *(ram_log_slice().as_mut_ptr() as *mut RingHeader) }
+}
+
+/// Computes a CRC32 (IEEE 802.3, reflected, poly 0xEDB88320) over a sequence
+/// of byte chunks. Bitwise (no lookup table) to keep the `no_std` footprint
+/// small.
+fn crc32(chunks: &[&[u8]]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for chunk in chunks {
+        for &byte in *chunk {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
             }
         }
     }
+    !crc
+}
 
-    pub fn filename(&self) -> &'static str {
-        unsafe {
-            let panic_info_ram = ram_log_slice();
-            let panic_filename_start = panic_info_ram.as_ptr().offset(size_of::<PanicInfoMeta>() as isize);
-            let panic_filename = core::slice::from_raw_parts(panic_filename_start, self.filename_len as usize);
-            core::str::from_utf8_unchecked(panic_filename)
+/// Slot offsets must stay 4-byte aligned: every slot is read straight off
+/// its pointer as a `PanicInfoMeta`/`HardFaultRegs`/`OomInfo`, which traps
+/// on an unaligned address on cores like the Cortex-M0 or an M3/M4 with
+/// `UNALIGN_TRP` set.
+const fn align_up_4(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+const fn align_down_4(len: usize) -> usize {
+    len & !3
+}
+
+fn header_len() -> usize {
+    align_up_4(size_of::<RingHeader>())
+}
+
+fn slot_len() -> usize {
+    align_down_4((ram_log_slice().len() - header_len()) / RING_CAPACITY)
+}
+
+fn slot_offset(idx: usize) -> usize {
+    header_len() + idx * slot_len()
+}
+
+fn slot(idx: usize) -> &'static mut [u8] {
+    let start = slot_offset(idx);
+    let len = slot_len();
+    &mut ram_log_slice()[start..start + len]
+}
+
+impl PanicInfoMeta {
+    /// Drains the ring buffer, newest record first, validating each entry's
+    /// CRC32 independently and clearing it as it is yielded. Returns an
+    /// empty iterator if the region's magic/version don't match this build,
+    /// since its contents were written by an incompatible layout.
+    pub fn detect_and_reset() -> RecoveredPanics {
+        let header = ring_header();
+        if !header.is_valid() {
+            return RecoveredPanics { next_idx: 0, remaining: 0 };
         }
+        let write_idx = header.write_idx as usize % RING_CAPACITY;
+        let newest = if write_idx == 0 { RING_CAPACITY - 1 } else { write_idx - 1 };
+        RecoveredPanics { next_idx: newest, remaining: RING_CAPACITY }
     }
+}
 
-    pub fn message(&self) -> &'static str {
-        unsafe {
-            let panic_info_ram = ram_log_slice();
-            let panic_message_start = panic_info_ram.as_ptr().offset((size_of::<PanicInfoMeta>() + self.filename_len as usize) as isize);
-            let panic_message = core::slice::from_raw_parts(panic_message_start, self.message_len as usize);
-            core::str::from_utf8_unchecked(panic_message)
+/// Iterator over valid crash records in the ring buffer, newest-first.
+pub struct RecoveredPanics {
+    next_idx: usize,
+    remaining: usize,
+}
+
+impl Iterator for RecoveredPanics {
+    type Item = RecoveredPanic;
+
+    fn next(&mut self) -> Option<RecoveredPanic> {
+        while self.remaining > 0 {
+            let idx = self.next_idx;
+            self.remaining -= 1;
+            self.next_idx = if idx == 0 { RING_CAPACITY - 1 } else { idx - 1 };
+
+            let payload_offset = slot_offset(idx) + size_of::<PanicInfoMeta>();
+            let entry = slot(idx);
+            let mut meta = unsafe { *(entry.as_ptr() as *const PanicInfoMeta) };
+            let cause = match FaultCause::from_u8(meta.cause) {
+                Some(cause) => cause,
+                None => continue,
+            };
+            let payload_len = meta.payload_len();
+            if size_of::<PanicInfoMeta>() + payload_len > entry.len() {
+                continue;
+            }
+            let stored_crc32 = meta.crc32;
+            meta.crc32 = 0;
+            let meta_bytes = unsafe { core::slice::from_raw_parts(&meta as *const _ as *const u8, size_of::<PanicInfoMeta>()) };
+            let payload_bytes = &entry[size_of::<PanicInfoMeta>()..size_of::<PanicInfoMeta>() + payload_len];
+            if crc32(&[meta_bytes, payload_bytes]) != stored_crc32 {
+                continue;
+            }
+            meta.crc32 = stored_crc32;
+            let recovered = match cause {
+                FaultCause::Panic => RecoveredPanic::Panic(PanicRecord { meta, payload_offset }),
+                FaultCause::HardFault => {
+                    let regs = unsafe { *(entry.as_ptr().offset(size_of::<PanicInfoMeta>() as isize) as *const HardFaultRegs) };
+                    RecoveredPanic::HardFault(regs)
+                }
+                FaultCause::Oom => {
+                    let info = unsafe { *(entry.as_ptr().offset(size_of::<PanicInfoMeta>() as isize) as *const OomInfo) };
+                    RecoveredPanic::Oom(info)
+                }
+            };
+            unsafe {
+                core::ptr::write_bytes(entry.as_mut_ptr(), 0, size_of::<PanicInfoMeta>());
+            }
+            return Some(recovered);
+        }
+        None
+    }
+}
+
+/// Drains the RAM ring buffer and hands each recovered record (newest
+/// first) to the reporter registered via `set_panic_reporter()`. Call this
+/// early in boot, before anything overwrites the RAM log region. A no-op if
+/// no reporter has been registered.
+pub fn report_last_panic() {
+    let reporter = match unsafe { PANIC_REPORTER } {
+        Some(reporter) => reporter,
+        None => return,
+    };
+    for recovered in PanicInfoMeta::detect_and_reset() {
+        reporter(&recovered);
+    }
+}
+
+/// Default formatting for a recovered crash record, mirroring how
+/// `core::panic::Location` displays (`panic at file:line:col: msg`) plus a
+/// terse register dump for hardware faults and the failed size/align for
+/// allocation failures. Write
+/// the result to any `core::fmt::Write` sink so this composes with RTT, a
+/// UART, or defmt without the crate depending on any of them directly.
+pub fn write_recovered_panic(w: &mut dyn Write, recovered: &RecoveredPanic) -> core::fmt::Result {
+    match recovered {
+        RecoveredPanic::Panic(record) => {
+            write!(w, "panic at {}:{}:{}: {}", record.filename(), record.line(), record.column(), record.message())?;
+            for frame in record.frames() {
+                write!(w, "\n    at {:#010x}", frame)?;
+            }
+            Ok(())
+        }
+        RecoveredPanic::HardFault(regs) => {
+            write!(
+                w,
+                "hardfault: cfsr={:#010x} hfsr={:#010x} mmfar={:#010x} bfar={:#010x} pc={:#010x} lr={:#010x}",
+                regs.cfsr, regs.hfsr, regs.mmfar, regs.bfar, regs.pc, regs.lr
+            )
+        }
+        RecoveredPanic::Oom(info) => {
+            write!(w, "oom: failed to allocate {} bytes (align {})", info.size, info.align)
+        }
+    }
+}
+
+/// Writes one ring buffer entry with `fill`, advances the write index,
+/// blinks the panic LED (if set) and resets. `fill` is handed the slot's
+/// payload region (the bytes after the `PanicInfoMeta` it is building) to
+/// write filename/message or fault registers into.
+unsafe fn record_and_reset(fill: impl FnOnce(&mut PanicInfoMeta, &mut [u8])) -> ! {
+    let header = ring_header();
+    // A header from an incompatible build (or an unwritten region) can't be
+    // trusted, so start the ring over rather than honor its write_idx/wrap_count.
+    let fresh = !header.is_valid();
+    let idx = if fresh { 0 } else { header.write_idx as usize % RING_CAPACITY };
+    let wrap_count = if fresh { 0 } else { header.wrap_count };
+
+    let entry = slot(idx);
+    let mut meta = PanicInfoMeta::default();
+    fill(&mut meta, &mut entry[size_of::<PanicInfoMeta>()..]);
+
+    let payload_len = meta.payload_len();
+    meta.crc32 = 0;
+    let meta_bytes = core::slice::from_raw_parts(&meta as *const _ as *const u8, size_of::<PanicInfoMeta>());
+    let payload_bytes = &entry[size_of::<PanicInfoMeta>()..size_of::<PanicInfoMeta>() + payload_len];
+    meta.crc32 = crc32(&[meta_bytes, payload_bytes]);
+
+    core::ptr::copy_nonoverlapping(
+        &meta as *const _ as *const u8,
+        entry.as_mut_ptr(),
+        size_of::<PanicInfoMeta>()
+    );
+
+    header.magic = RING_MAGIC;
+    header.layout_version = RING_LAYOUT_VERSION;
+    header.write_idx = ((idx + 1) % RING_CAPACITY) as u8;
+    header.wrap_count = if idx + 1 == RING_CAPACITY { wrap_count.wrapping_add(1) } else { wrap_count };
+
+    match PANIC_LED_BLINKER {
+        Some(blinker) => blinker(),
+        None => {}
+    }
+    cortex_m::peripheral::SCB::sys_reset(); // -> !
+}
+
+cfg_if! {
+    if #[cfg(feature = "backtrace")] {
+        /// Max return addresses captured per panic; bounds both the stack
+        /// walk and the RAM used, so it can't run away or blow out a slot.
+        pub const MAX_BACKTRACE_FRAMES: usize = 8;
+
+        fn in_text_range(addr: u32) -> bool {
+            unsafe {
+                let text_start = &_stext as *const u8 as u32;
+                let text_end = &_etext as *const u8 as u32;
+                let addr = addr & !1; // strip the Thumb bit
+                addr >= text_start && addr < text_end
+            }
+        }
+
+        /// Collects up to `MAX_BACKTRACE_FRAMES` return addresses, innermost
+        /// call first. With the `frame-pointer` feature, follows the r7
+        /// frame-pointer chain; otherwise conservatively scans words between
+        /// the current SP and `_stack_start` that land inside `.text`.
+        fn capture_backtrace() -> ([u32; MAX_BACKTRACE_FRAMES], usize) {
+            let mut frames = [0u32; MAX_BACKTRACE_FRAMES];
+            let mut len = 0usize;
+            cfg_if! {
+                if #[cfg(feature = "frame-pointer")] {
+                    let mut fp: u32;
+                    unsafe { core::arch::asm!("mov {}, r7", out(reg) fp); }
+                    while len < MAX_BACKTRACE_FRAMES && fp != 0 && fp % 4 == 0 {
+                        let lr = unsafe { core::ptr::read((fp + 4) as *const u32) };
+                        if !in_text_range(lr) {
+                            break;
+                        }
+                        frames[len] = lr;
+                        len += 1;
+                        fp = unsafe { core::ptr::read(fp as *const u32) };
+                    }
+                } else {
+                    let stack_start = unsafe { &_stack_start as *const u8 as u32 };
+                    let mut sp: u32;
+                    unsafe { core::arch::asm!("mov {}, sp", out(reg) sp); }
+                    while sp < stack_start && len < MAX_BACKTRACE_FRAMES {
+                        let candidate = unsafe { core::ptr::read(sp as *const u32) };
+                        if in_text_range(candidate) {
+                            frames[len] = candidate;
+                            len += 1;
+                        }
+                        sp += size_of::<u32>() as u32;
+                    }
+                }
+            }
+            (frames, len)
         }
     }
 }
@@ -100,53 +510,91 @@ impl PanicInfoMeta {
 #[inline(never)]
 #[panic_handler]
 fn panic(info: &PanicInfo) -> ! {
-    // Format panic message into PANIC_INFO_RAM region
     unsafe {
-        let panic_info_ram = ram_log_slice();
-
-        let mut panic_info_meta = PanicInfoMeta::default();
-        match info.location() {
-            Some(l) => {
-                let filename_len = if l.file().len() > 255 { 255 } else { l.file().len() as u8 };
-                panic_info_meta.filename_len = filename_len;
-                panic_info_meta.line = l.line();
-                panic_info_meta.column = l.column();
-                core::ptr::copy_nonoverlapping(
-                    l.file() as *const _ as *mut u8,
-                    panic_info_ram.as_mut_ptr().offset(size_of::<PanicInfoMeta>() as isize),
-                    filename_len as usize
-                );
-            },
-            None => {}
-        }
-        cfg_if! {
-            if #[cfg(not(feature = "minimal"))] {
-                let message_start_idx = size_of::<PanicInfoMeta>() + panic_info_meta.filename_len as usize;
-                let mut cursor = DumbCursor {
-                    buf: core::slice::from_raw_parts_mut(
-                        panic_info_ram.as_mut_ptr().offset(message_start_idx as isize),
-                        panic_info_ram.len() - message_start_idx
-                    ),
-                    idx: 0
-                };
-                let _ = write!(cursor, "{}", info);
-                panic_info_meta.message_len = cursor.idx as u16;
+        record_and_reset(|meta, payload| {
+            meta.cause = FaultCause::Panic as u8;
+            if let Some(l) = info.location() {
+                let filename_len = core::cmp::min(core::cmp::min(l.file().len(), 255), payload.len()) as u8;
+                meta.filename_len = filename_len;
+                meta.line = l.line();
+                meta.column = l.column();
+                payload[..filename_len as usize].copy_from_slice(&l.file().as_bytes()[..filename_len as usize]);
             }
-        }
+            cfg_if! {
+                if #[cfg(not(feature = "minimal"))] {
+                    let mut cursor = DumbCursor {
+                        buf: &mut payload[meta.filename_len as usize..],
+                        idx: 0
+                    };
+                    let _ = write!(cursor, "{}", info);
+                    meta.message_len = cursor.idx as u16;
+                }
+            }
+            cfg_if! {
+                if #[cfg(feature = "backtrace")] {
+                    let (frames, frames_found) = capture_backtrace();
+                    let frames_start = meta.filename_len as usize + meta.message_len as usize;
+                    let frames_room = (payload.len() - frames_start) / size_of::<u32>();
+                    let frames_len = core::cmp::min(frames_found, frames_room);
+                    let frames_bytes = core::slice::from_raw_parts(
+                        frames.as_ptr() as *const u8,
+                        frames_len * size_of::<u32>()
+                    );
+                    payload[frames_start..frames_start + frames_bytes.len()].copy_from_slice(frames_bytes);
+                    meta.frames_len = frames_len as u8;
+                }
+            }
+        })
+    }
+}
 
-        for i in size_of::<PanicInfoMeta>()..panic_info_ram.len() {
-            panic_info_meta.xor = panic_info_meta.xor ^ *panic_info_ram.get_unchecked(i);
-        }
+const SCB_CFSR: *const u32 = 0xE000ED28 as *const u32;
+const SCB_HFSR: *const u32 = 0xE000ED2C as *const u32;
+const SCB_MMFAR: *const u32 = 0xE000ED34 as *const u32;
+const SCB_BFAR: *const u32 = 0xE000ED38 as *const u32;
 
-        core::ptr::copy_nonoverlapping(
-            &panic_info_meta as *const _ as *mut u8,
-            panic_info_ram.as_mut_ptr(),
-            core::mem::size_of::<PanicInfoMeta>()
-        );
-        match PANIC_LED_BLINKER {
-            Some(blinker) => blinker(),
-            None => {}
-        }
+const CFSR_MMARVALID: u32 = 1 << 7;
+const CFSR_BFARVALID: u32 = 1 << 15;
+
+/// Captures CFSR/HFSR/MMFAR/BFAR and the stacked exception frame.
+#[exception]
+unsafe fn HardFault(ef: &ExceptionFrame) -> ! {
+    record_and_reset(|meta, payload| {
+        meta.cause = FaultCause::HardFault as u8;
+
+        let cfsr = core::ptr::read_volatile(SCB_CFSR);
+        let regs = HardFaultRegs {
+            cfsr,
+            hfsr: core::ptr::read_volatile(SCB_HFSR),
+            mmfar: if cfsr & CFSR_MMARVALID != 0 { core::ptr::read_volatile(SCB_MMFAR) } else { 0 },
+            bfar: if cfsr & CFSR_BFARVALID != 0 { core::ptr::read_volatile(SCB_BFAR) } else { 0 },
+            r0: ef.r0(),
+            r1: ef.r1(),
+            r2: ef.r2(),
+            r3: ef.r3(),
+            r12: ef.r12(),
+            lr: ef.lr(),
+            pc: ef.pc(),
+            xpsr: ef.xpsr(),
+        };
+        let regs_bytes = core::slice::from_raw_parts(&regs as *const _ as *const u8, size_of::<HardFaultRegs>());
+        let len = core::cmp::min(regs_bytes.len(), payload.len());
+        payload[..len].copy_from_slice(&regs_bytes[..len]);
+    })
+}
+
+/// Requires nightly for the unstable `alloc_error_handler` attribute.
+#[cfg(feature = "oom")]
+#[alloc_error_handler]
+fn oom(layout: core::alloc::Layout) -> ! {
+    unsafe {
+        record_and_reset(|meta, payload| {
+            meta.cause = FaultCause::Oom as u8;
+
+            let info = OomInfo { size: layout.size() as u32, align: layout.align() as u32 };
+            let info_bytes = core::slice::from_raw_parts(&info as *const _ as *const u8, size_of::<OomInfo>());
+            let len = core::cmp::min(info_bytes.len(), payload.len());
+            payload[..len].copy_from_slice(&info_bytes[..len]);
+        })
     }
-    cortex_m::peripheral::SCB::sys_reset(); // -> !
 }
\ No newline at end of file